@@ -29,8 +29,11 @@ pub struct NpackConfig {
     /// Environment variables for postinstall
     pub env: Option<HashMap<String, String>>,
 
-    /// Additional assets to include
-    pub assets: Option<Vec<String>>,
+    /// Assets to embed in the SEA (resource key → file path or glob, relative to the app)
+    pub assets: Option<HashMap<String, String>>,
+
+    /// Entry script that builds a V8 startup snapshot for faster boot
+    pub startup_snapshot: Option<String>,
 
     /// Scripts to include (like migrations)
     pub scripts: Option<Vec<String>>,
@@ -43,77 +46,185 @@ pub struct NpackConfig {
 
     /// S3 Secret for packages
     pub s3_secret: Option<String>,
+
+    /// S3 bucket to push the finished artifact to
+    pub s3_bucket: Option<String>,
+
+    /// S3 region (AWS region name, or the region label for S3-compatible hosts)
+    pub s3_region: Option<String>,
+
+    /// Custom S3 endpoint (for MinIO, DigitalOcean Spaces, …)
+    pub s3_endpoint: Option<String>,
+
+    /// Node runtime download cache settings
+    pub cache: Option<CacheConfig>,
+
+    /// npm scope/org for generated wrapper packages (e.g. `@acme`)
+    pub npm_org: Option<String>,
+
+    /// npm package name for generated wrapper packages
+    pub npm_name: Option<String>,
+
+    /// Whether `npm publish` the generated packages
+    pub npm_publish: Option<bool>,
+
+    /// Explicit list of target tuples to build (e.g. `["linux-x64", "macos-arm64"]`)
+    pub targets: Option<Vec<String>>,
+
+    /// Fail the build when inputs drift from `npack.lock` instead of warning
+    pub frozen: Option<bool>,
+}
+
+/// Settings for the persistent Node runtime cache.
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct CacheConfig {
+    /// Whether the cache is used (defaults to true; `false` is equivalent to `--no-cache`)
+    pub enabled: Option<bool>,
+
+    /// Override the cache root (defaults to `dirs::cache_dir()/npack/node`)
+    pub dir: Option<PathBuf>,
+}
+
+/// Compose two values, letting `other` override where it carries a value.
+///
+/// Used to layer config sources — global → project → CLI — so each layer only
+/// overrides the fields it actually sets.
+pub trait Merge {
+    fn merge(&mut self, other: Self);
 }
 
 impl NpackConfig {
-    /// Load config from file
+    /// Load config from file, dispatching on the file extension.
     pub fn from_file(path: &PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
         let content = fs::read_to_string(path)?;
-        let config: NpackConfig = serde_json::from_str(&content)?;
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let config: NpackConfig = match ext.as_str() {
+            "toml" => toml::from_str(&content)?,
+            "yaml" | "yml" => serde_yaml::from_str(&content)?,
+            _ => serde_json::from_str(&content)?,
+        };
         Ok(config)
     }
 
-    /// Try to find config in current directory
-    pub fn find_in_cwd() -> Option<Self> {
-        let config_names = ["npack.config.json", ".npackrc", "npack.json"];
+    /// Discover and layer configs: a global config, then every project config
+    /// from the filesystem root down to the current directory (innermost wins).
+    pub fn discover() -> Self {
+        let mut config = Self::default();
 
-        for name in &config_names {
-            let path = PathBuf::from(name);
-            if path.exists() {
-                if let Ok(config) = Self::from_file(&path) {
-                    println!("📋 Loaded config from: {}", name);
-                    return Some(config);
-                }
+        if let Some(path) = Self::global_config_path() {
+            if let Ok(global) = Self::from_file(&path) {
+                println!("📋 Loaded global config from: {}", path.display());
+                config.merge(global);
             }
         }
 
-        None
-    }
-
-    /// Merge with CLI args (CLI args override config)
-    pub fn merge_with_args(&mut self, args: &Args) {
-        // Source (позиционный аргумент или из конфига)
-        if args.source.is_some() {
-            self.source = args.source.clone();
-        }
-
-        // Entry point
-        if args.entry.is_some() {
-            self.entry = args.entry.clone();
+        for path in Self::project_chain() {
+            if let Ok(project) = Self::from_file(&path) {
+                println!("📋 Loaded config from: {}", path.display());
+                config.merge(project);
+            }
         }
 
-        // Platform
-        if let Some(platform) = &args.platform {
-            self.platform = Some(platform.clone());
-        }
+        config
+    }
 
-        // Node version
-        if let Some(node_version) = &args.node_version {
-            self.node_version = Some(node_version.clone());
-        }
+    /// Path to the first existing global config under `~/.config/npack`.
+    fn global_config_path() -> Option<PathBuf> {
+        let dir = dirs::config_dir()?.join("npack");
+        ["config.toml", "config.yaml", "config.yml", "config.json"]
+            .iter()
+            .map(|n| dir.join(n))
+            .find(|p| p.exists())
+    }
 
-        // Output directory
-        if let Some(output) = &args.output {
-            self.output = Some(output.clone());
+    /// Project config files from the filesystem root down to the cwd, so the
+    /// closest config is applied last and therefore wins.
+    fn project_chain() -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        if let Ok(cwd) = std::env::current_dir() {
+            let mut current = Some(cwd);
+            while let Some(dir) = current {
+                dirs.push(dir.clone());
+                current = dir.parent().map(|p| p.to_path_buf());
+            }
         }
+        dirs.reverse();
+
+        dirs.into_iter()
+            .filter_map(|dir| {
+                Self::CONFIG_NAMES
+                    .iter()
+                    .map(|n| dir.join(n))
+                    .find(|p| p.exists())
+            })
+            .collect()
+    }
 
-        // Postinstall flag
-        if args.run_postinstall {
-            self.run_postinstall = Some(true);
-        }
+    /// Candidate config file names, in priority order.
+    const CONFIG_NAMES: [&'static str; 6] = [
+        "npack.config.json",
+        "npack.config.toml",
+        "npack.config.yaml",
+        "npack.config.yml",
+        ".npackrc",
+        "npack.json",
+    ];
+
+    /// Locate a config file in the current directory without loading it.
+    pub fn discover_config_path() -> Option<PathBuf> {
+        Self::CONFIG_NAMES
+            .iter()
+            .map(PathBuf::from)
+            .find(|p| p.exists())
+    }
 
-        // Database connection
-        if args.db_connection.is_some() {
-            self.db_connection = args.db_connection.clone();
+    /// Resolve `${VAR}`/`$VAR` placeholders and `secret://` references in place.
+    ///
+    /// Run after the config is assembled: every string field has environment
+    /// placeholders expanded from the process environment, and the credential
+    /// fields additionally support a `secret://<name>` scheme that pulls the
+    /// value from the OS keyring, so configs can be committed without secrets.
+    pub fn resolve(&mut self) {
+        expand_opt(&mut self.source);
+        expand_opt(&mut self.entry);
+        expand_opt(&mut self.platform);
+        expand_opt(&mut self.node_version);
+        expand_opt(&mut self.db_connection);
+        expand_opt(&mut self.s3_key);
+        expand_opt(&mut self.s3_secret);
+        expand_opt(&mut self.s3_bucket);
+        expand_opt(&mut self.s3_region);
+        expand_opt(&mut self.s3_endpoint);
+        expand_opt(&mut self.startup_snapshot);
+        expand_opt(&mut self.npm_org);
+        expand_opt(&mut self.npm_name);
+
+        if let Some(env) = &mut self.env {
+            for value in env.values_mut() {
+                *value = expand_env(value);
+            }
         }
-
-        // S3 credentials
-        if args.s3_key.is_some() {
-            self.s3_key = args.s3_key.clone();
+        if let Some(assets) = &mut self.assets {
+            for value in assets.values_mut() {
+                *value = expand_env(value);
+            }
         }
-        if args.s3_secret.is_some() {
-            self.s3_secret = args.s3_secret.clone();
+        for list in [&mut self.scripts, &mut self.targets] {
+            if let Some(items) = list {
+                for item in items.iter_mut() {
+                    *item = expand_env(item);
+                }
+            }
         }
+
+        // Credential fields may indirect through the OS keyring.
+        resolve_secret(&mut self.s3_key);
+        resolve_secret(&mut self.s3_secret);
+        resolve_secret(&mut self.db_connection);
     }
 
     /// Get final source (from config or error)
@@ -170,11 +281,92 @@ impl NpackConfig {
         env
     }
 
-    /// Get assets patterns
-    pub fn get_assets(&self) -> Vec<String> {
+    /// Is the Node runtime cache enabled? (defaults to true)
+    pub fn cache_enabled(&self) -> bool {
+        self.cache
+            .as_ref()
+            .and_then(|c| c.enabled)
+            .unwrap_or(true)
+    }
+
+    /// Get the cache root directory (config override or platform cache dir)
+    pub fn get_cache_dir(&self) -> Option<PathBuf> {
+        if let Some(dir) = self.cache.as_ref().and_then(|c| c.dir.clone()) {
+            return Some(dir);
+        }
+        dirs::cache_dir().map(|d| d.join("npack").join("node"))
+    }
+
+    /// Get the SEA asset map (resource key → file path or glob)
+    pub fn get_sea_assets(&self) -> HashMap<String, String> {
         self.assets.clone().unwrap_or_default()
     }
 
+    /// Get asset path patterns (the values of the asset map)
+    pub fn get_assets(&self) -> Vec<String> {
+        self.assets
+            .as_ref()
+            .map(|a| a.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the startup snapshot entry script, if configured
+    pub fn get_startup_snapshot(&self) -> Option<String> {
+        self.startup_snapshot.clone()
+    }
+
+    /// Get the S3 access key
+    pub fn get_s3_key(&self) -> Option<String> {
+        self.s3_key.clone()
+    }
+
+    /// Get the S3 secret
+    pub fn get_s3_secret(&self) -> Option<String> {
+        self.s3_secret.clone()
+    }
+
+    /// Get the upload bucket
+    pub fn get_s3_bucket(&self) -> Option<String> {
+        self.s3_bucket.clone()
+    }
+
+    /// Get the S3 region (defaults to `us-east-1` like the AWS SDKs)
+    pub fn get_s3_region(&self) -> String {
+        self.s3_region
+            .clone()
+            .unwrap_or_else(|| "us-east-1".to_string())
+    }
+
+    /// Get the custom S3 endpoint, if any
+    pub fn get_s3_endpoint(&self) -> Option<String> {
+        self.s3_endpoint.clone()
+    }
+
+    /// Get the npm scope/org for generated wrapper packages
+    pub fn get_npm_org(&self) -> Option<String> {
+        self.npm_org.clone()
+    }
+
+    /// Get the npm package name for generated wrapper packages
+    pub fn get_npm_name(&self) -> Option<String> {
+        self.npm_name.clone()
+    }
+
+    /// Should the generated npm packages be published?
+    pub fn should_publish(&self) -> bool {
+        self.npm_publish.unwrap_or(false)
+    }
+
+    /// Get the explicit target tuple list
+    pub fn get_targets(&self) -> Vec<String> {
+        self.targets.clone().unwrap_or_default()
+    }
+
+    /// Should input drift from `npack.lock` fail the build?
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.unwrap_or(false)
+    }
+
     /// Get scripts patterns
     pub fn get_scripts(&self) -> Vec<String> {
         self.scripts.clone().unwrap_or_default()
@@ -186,15 +378,63 @@ impl NpackConfig {
             return Err("Source is required".to_string());
         }
 
-        // Проверка платформы
+        // Any unresolved secret:// reference means the keyring lookup failed.
+        for (name, field) in [
+            ("s3_key", &self.s3_key),
+            ("s3_secret", &self.s3_secret),
+            ("db_connection", &self.db_connection),
+        ] {
+            if let Some(value) = field {
+                if value.starts_with("secret://") {
+                    return Err(format!(
+                        "Could not resolve {} from keyring: {}",
+                        name, value
+                    ));
+                }
+            }
+        }
+
+        // Каждый target tuple должен соответствовать известной паре OS/arch
+        for target in self.get_targets() {
+            let (os, arch) = target.split_once('-').ok_or_else(|| {
+                format!("Invalid target tuple: {} (expected <os>-<arch>)", target)
+            })?;
+            match os {
+                "linux" | "macos" | "windows" => {}
+                _ => return Err(format!("Invalid OS in target tuple: {}", target)),
+            }
+            match arch {
+                "x64" | "arm64" => {}
+                _ => return Err(format!("Invalid architecture in target tuple: {}", target)),
+            }
+        }
+
+        // S3 source требует учётных данных
+        if let Some(source) = &self.source {
+            if source.starts_with("s3://") && (self.s3_key.is_none() || self.s3_secret.is_none()) {
+                return Err("s3:// source requires s3_key and s3_secret".to_string());
+            }
+        }
+
+        // Проверка платформы (os или os-arch, напр. "macos-arm64")
         if let Some(platform) = &self.platform {
             match platform.as_str() {
-                "host" | "windows" | "linux" | "macos" => {}
-                _ => {
-                    return Err(format!(
-                        "Invalid platform: {}. Use: host, windows, linux, or macos",
-                        platform
-                    ))
+                "host" | "all" | "windows" | "linux" | "macos" => {}
+                other => {
+                    let (os, arch) = other.split_once('-').ok_or_else(|| {
+                        format!(
+                            "Invalid platform: {}. Use: host, all, windows, linux, macos, or <os>-<arch>",
+                            other
+                        )
+                    })?;
+                    match os {
+                        "windows" | "linux" | "macos" => {}
+                        _ => return Err(format!("Invalid OS in target: {}", other)),
+                    }
+                    match arch {
+                        "x64" | "arm64" => {}
+                        _ => return Err(format!("Invalid architecture in target: {}", other)),
+                    }
                 }
             }
         }
@@ -203,32 +443,231 @@ impl NpackConfig {
     }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-
-//     #[test]
-//     fn test_default_config() {
-//         let config = NpackConfig::default();
-//         assert_eq!(config.get_platform(), "host");
-//         assert_eq!(config.get_node_version(), "24.12.0");
-//         assert_eq!(config.get_output(), "./dist");
-//     }
-
-//     #[test]
-//     fn test_env_vars_merge() {
-//         let mut config = NpackConfig::default();
-//         config.db_connection = Some("postgres://localhost".to_string());
-//         config.s3_key = Some("key123".to_string());
-
-//         let env = config.get_env_vars();
-//         assert_eq!(
-//             env.get("DB_CONNECTION_STRING"),
-//             Some(&"postgres://localhost".to_string())
-//         );
-//         assert_eq!(
-//             env.get("PACKAGES_STORAGE_S3_KEY"),
-//             Some(&"key123".to_string())
-//         );
-//     }
-// }
+/// Keyring service name under which npack secrets are stored.
+const KEYRING_SERVICE: &str = "npack";
+
+/// Expand `${VAR}` / `$VAR` placeholders in an optional string field.
+fn expand_opt(slot: &mut Option<String>) {
+    if let Some(value) = slot {
+        *value = expand_env(value);
+    }
+}
+
+/// Replace `${VAR}` and `$VAR` with the matching process-environment value.
+/// Unknown variables expand to an empty string, as a shell would.
+fn expand_env(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(idx) = rest.find('$') {
+        out.push_str(&rest[..idx]);
+        let after = &rest[idx + 1..];
+
+        if let Some(inner) = after.strip_prefix('{') {
+            // ${VAR}
+            match inner.find('}') {
+                Some(end) => {
+                    out.push_str(&std::env::var(&inner[..end]).unwrap_or_default());
+                    rest = &inner[end + 1..];
+                }
+                None => {
+                    // Unterminated — emit the rest verbatim.
+                    out.push('$');
+                    rest = after;
+                }
+            }
+        } else {
+            // $VAR — a name starts with a letter or underscore and runs up to the
+            // first non-alphanumeric/underscore char; otherwise `$` is literal.
+            let starts_name = after
+                .chars()
+                .next()
+                .map_or(false, |c| c.is_alphabetic() || c == '_');
+            let end = if starts_name {
+                after
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len())
+            } else {
+                0
+            };
+            if end == 0 {
+                out.push('$');
+                rest = after;
+            } else {
+                out.push_str(&std::env::var(&after[..end]).unwrap_or_default());
+                rest = &after[end..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Resolve a `secret://<name>` reference from the OS keyring, leaving the
+/// value untouched on a miss so [`NpackConfig::validate`] can report it.
+fn resolve_secret(slot: &mut Option<String>) {
+    if let Some(value) = slot {
+        if let Some(name) = value.strip_prefix("secret://") {
+            if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, name) {
+                if let Ok(password) = entry.get_password() {
+                    *value = password;
+                }
+            }
+        }
+    }
+}
+
+/// Helper: let `other` override `self` only where it carries a value.
+fn take<T>(slot: &mut Option<T>, other: Option<T>) {
+    if other.is_some() {
+        *slot = other;
+    }
+}
+
+impl Merge for NpackConfig {
+    fn merge(&mut self, other: Self) {
+        take(&mut self.source, other.source);
+        take(&mut self.entry, other.entry);
+        take(&mut self.platform, other.platform);
+        take(&mut self.node_version, other.node_version);
+        take(&mut self.output, other.output);
+        take(&mut self.run_postinstall, other.run_postinstall);
+        take(&mut self.env, other.env);
+        take(&mut self.assets, other.assets);
+        take(&mut self.startup_snapshot, other.startup_snapshot);
+        take(&mut self.scripts, other.scripts);
+        take(&mut self.db_connection, other.db_connection);
+        take(&mut self.s3_key, other.s3_key);
+        take(&mut self.s3_secret, other.s3_secret);
+        take(&mut self.s3_bucket, other.s3_bucket);
+        take(&mut self.s3_region, other.s3_region);
+        take(&mut self.s3_endpoint, other.s3_endpoint);
+        take(&mut self.cache, other.cache);
+        take(&mut self.npm_org, other.npm_org);
+        take(&mut self.npm_name, other.npm_name);
+        take(&mut self.npm_publish, other.npm_publish);
+        take(&mut self.targets, other.targets);
+        take(&mut self.frozen, other.frozen);
+    }
+}
+
+impl From<&Args> for NpackConfig {
+    /// Project CLI args into a sparse config that forms the top merge layer.
+    fn from(args: &Args) -> Self {
+        let cache = if args.no_cache {
+            Some(CacheConfig {
+                enabled: Some(false),
+                dir: None,
+            })
+        } else {
+            None
+        };
+
+        NpackConfig {
+            source: args.source.clone(),
+            entry: args.entry.clone(),
+            platform: args.platform.clone(),
+            node_version: args.node_version.clone(),
+            output: args.output.clone(),
+            run_postinstall: if args.run_postinstall { Some(true) } else { None },
+            db_connection: args.db_connection.clone(),
+            s3_key: args.s3_key.clone(),
+            s3_secret: args.s3_secret.clone(),
+            cache,
+            frozen: if args.frozen { Some(true) } else { None },
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = NpackConfig::default();
+        assert_eq!(config.get_platform(), "host");
+        assert_eq!(config.get_node_version(), "24.12.0");
+        assert_eq!(config.get_output(), PathBuf::from("./dist"));
+    }
+
+    #[test]
+    fn test_env_vars_merge() {
+        let mut config = NpackConfig::default();
+        config.db_connection = Some("postgres://localhost".to_string());
+        config.s3_key = Some("key123".to_string());
+
+        let env = config.get_env_vars();
+        assert_eq!(
+            env.get("DB_CONNECTION_STRING"),
+            Some(&"postgres://localhost".to_string())
+        );
+        assert_eq!(
+            env.get("PACKAGES_STORAGE_S3_KEY"),
+            Some(&"key123".to_string())
+        );
+    }
+
+    #[test]
+    fn expand_env_substitutes_both_syntaxes() {
+        std::env::set_var("NPACK_TEST_EXPAND", "world");
+        assert_eq!(expand_env("hello $NPACK_TEST_EXPAND!"), "hello world!");
+        assert_eq!(expand_env("hello ${NPACK_TEST_EXPAND}!"), "hello world!");
+        std::env::remove_var("NPACK_TEST_EXPAND");
+    }
+
+    #[test]
+    fn expand_env_unknown_becomes_empty_and_keeps_literals() {
+        std::env::remove_var("NPACK_TEST_MISSING");
+        assert_eq!(expand_env("a${NPACK_TEST_MISSING}b"), "ab");
+        // A lone `$` with no name is left verbatim.
+        assert_eq!(expand_env("cost is $5"), "cost is $5");
+    }
+
+    #[test]
+    fn resolve_secret_leaves_plain_values_untouched() {
+        let mut slot = Some("plain-value".to_string());
+        resolve_secret(&mut slot);
+        assert_eq!(slot.as_deref(), Some("plain-value"));
+    }
+
+    #[test]
+    fn resolve_secret_keeps_reference_on_keyring_miss() {
+        // An absent keyring entry leaves the `secret://` reference in place so
+        // `validate` can surface the unresolved credential.
+        let mut slot = Some("secret://npack-test-definitely-absent".to_string());
+        resolve_secret(&mut slot);
+        assert_eq!(slot.as_deref(), Some("secret://npack-test-definitely-absent"));
+    }
+
+    #[test]
+    fn merge_overrides_only_set_fields() {
+        let mut base = NpackConfig {
+            platform: Some("linux".to_string()),
+            node_version: Some("20".to_string()),
+            ..Default::default()
+        };
+        let top = NpackConfig {
+            platform: Some("macos".to_string()),
+            ..Default::default()
+        };
+
+        base.merge(top);
+
+        // The set field wins; the unset one is preserved.
+        assert_eq!(base.platform.as_deref(), Some("macos"));
+        assert_eq!(base.node_version.as_deref(), Some("20"));
+    }
+
+    #[test]
+    fn merge_none_does_not_clear_existing() {
+        let mut base = NpackConfig {
+            source: Some("./app".to_string()),
+            ..Default::default()
+        };
+        base.merge(NpackConfig::default());
+        assert_eq!(base.source.as_deref(), Some("./app"));
+    }
+}
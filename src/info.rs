@@ -0,0 +1,235 @@
+//! `npack info` — a preflight diagnostic report.
+//!
+//! Mirrors the spirit of Tauri's `info` command: before a build fails deep
+//! inside `bundle_app` or `inject_sea_blob`, this surfaces the state of the
+//! toolchain and the target project so users can spot a missing tool or a
+//! misconfigured entry point up front.
+
+use crate::NpackConfig;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Run the diagnostic report against the resolved config.
+pub async fn run(config: &NpackConfig) -> Result<(), Box<dyn std::error::Error>> {
+    println!("📋 npack v{}\n", env!("CARGO_PKG_VERSION"));
+
+    println!("Environment");
+    println!("   OS/arch: {} / {}", std::env::consts::OS, std::env::consts::ARCH);
+    println!("   node:    {}", tool_version("node", &["--version"]));
+    println!("   npm:     {}", tool_version("npm", &["--version"]));
+    println!("   npx:     {}", tool_version("npx", &["--version"]));
+    println!("   git:     {}", tool_version("git", &["--version"]));
+    println!(
+        "   postject: {}",
+        if postject_resolvable() { "available" } else { "not found" }
+    );
+
+    println!("\nBuild configuration");
+    println!(
+        "   Config file: {}",
+        NpackConfig::discover_config_path()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "(none, using defaults/CLI)".to_string())
+    );
+    println!("   Platform: {}", config.get_platform());
+    println!("   Output: {:?}", config.get_output());
+    let node_version = config.get_node_version();
+    match crate::resolve_node_version(&node_version).await {
+        Ok(resolved) => println!("   Node target: {} (resolves to v{})", node_version, resolved),
+        Err(e) => println!("   Node target: {} (failed to resolve: {})", node_version, e),
+    }
+
+    // Compare the local Node against the configured target and flag drift.
+    let local = tool_version("node", &["--version"]);
+    let matches = local
+        .trim_start_matches('v')
+        .starts_with(node_version.trim_start_matches('v'));
+    println!(
+        "   Local node: {}",
+        if matches {
+            green(&local)
+        } else {
+            yellow(&format!("{} (differs from target {})", local, node_version))
+        }
+    );
+    match crate::get_bundler_path() {
+        Ok(path) => println!("   Bundler: {:?}", path),
+        Err(e) => println!("   Bundler: {}", e),
+    }
+
+    println!("\nProject");
+    match config.get_source() {
+        Ok(source) if !source.starts_with("http") && !source.starts_with("git@") => {
+            report_project(&PathBuf::from(&source), config);
+        }
+        Ok(source) => println!("   Source: {} (remote, clone to inspect)", source),
+        Err(e) => println!("   Source: {}", e),
+    }
+
+    Ok(())
+}
+
+/// Read the target `package.json` and report entry point, framework and scripts.
+fn report_project(app_path: &Path, config: &NpackConfig) {
+    let package_json = app_path.join("package.json");
+    if !package_json.exists() {
+        println!("   No package.json found at {:?}", app_path);
+        return;
+    }
+
+    let package: serde_json::Value = match std::fs::read_to_string(&package_json)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+    {
+        Some(p) => p,
+        None => {
+            println!("   Could not parse {:?}", package_json);
+            return;
+        }
+    };
+
+    let str_field = |key: &str| {
+        package
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("(unknown)")
+            .to_string()
+    };
+    println!("   Name: {}", str_field("name"));
+    println!("   Version: {}", str_field("version"));
+    println!("   Package manager: {}", detect_package_manager(app_path));
+    println!("   package.json main: {}", str_field("main"));
+    if let Some(bin) = package.get("bin") {
+        println!("   package.json bin: {}", bin);
+    }
+
+    let entry = config
+        .get_entry()
+        .or_else(|| {
+            package
+                .get("main")
+                .and_then(|m| m.as_str())
+                .map(|s| s.to_string())
+        })
+        .unwrap_or_else(|| "(not declared)".to_string());
+
+    // Highlight a missing entry file, the most common cause of a failed build.
+    let entry_exists = entry != "(not declared)" && app_path.join(&entry).exists();
+    println!(
+        "   Entry point: {}",
+        if entry_exists {
+            green(&entry)
+        } else if entry == "(not declared)" {
+            yellow(&entry)
+        } else {
+            red(&format!("{} (file not found)", entry))
+        }
+    );
+    println!("   Framework: {}", detect_framework(&package));
+
+    let has_babel = dependency_keys(&package, "devDependencies")
+        .into_iter()
+        .any(|k| k.starts_with("@babel/") || k == "babel-cli");
+    println!("   Babel: {}", if has_babel { "yes" } else { "no" });
+
+    let scripts = package
+        .get("scripts")
+        .and_then(|s| s.as_object())
+        .cloned()
+        .unwrap_or_default();
+    println!(
+        "   compile script: {}",
+        if scripts.contains_key("compile") { "yes" } else { "no" }
+    );
+    println!(
+        "   postinstall script: {}",
+        if scripts.contains_key("postinstall") { "yes" } else { "no" }
+    );
+}
+
+/// Detect the package manager from lockfiles in the project directory.
+fn detect_package_manager(app_path: &Path) -> &'static str {
+    if app_path.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if app_path.join("yarn.lock").exists() {
+        "yarn"
+    } else if app_path.join("bun.lockb").exists() {
+        "bun"
+    } else if app_path.join("package-lock.json").exists() {
+        "npm"
+    } else {
+        "npm (no lockfile)"
+    }
+}
+
+/// Infer the application framework from its declared dependencies.
+fn detect_framework(package: &serde_json::Value) -> &'static str {
+    let deps = dependency_keys(package, "dependencies");
+    let has = |name: &str| deps.iter().any(|k| k == name);
+
+    if has("next") {
+        "Next.js"
+    } else if has("react") {
+        "React"
+    } else if has("express") {
+        "Express"
+    } else if has("@nestjs/core") {
+        "NestJS"
+    } else if has("fastify") {
+        "Fastify"
+    } else {
+        "unknown"
+    }
+}
+
+/// Collect the dependency names from a given section of `package.json`.
+fn dependency_keys(package: &serde_json::Value, section: &str) -> Vec<String> {
+    package
+        .get(section)
+        .and_then(|d| d.as_object())
+        .map(|o| o.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Query a tool's version string, or report that it is missing from PATH.
+fn tool_version(tool: &str, args: &[&str]) -> String {
+    let program = if cfg!(windows) {
+        format!("{}.cmd", tool)
+    } else {
+        tool.to_string()
+    };
+
+    // npm/npx/node ship as plain binaries on unix; only the *.cmd shim is
+    // windows-specific, so fall back to the bare name when that fails.
+    let output = Command::new(&program)
+        .args(args)
+        .output()
+        .or_else(|_| Command::new(tool).args(args).output());
+
+    match output {
+        Ok(out) if out.status.success() => {
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        }
+        _ => "not found".to_string(),
+    }
+}
+
+fn green(s: &str) -> String {
+    format!("\x1b[32m{}\x1b[0m", s)
+}
+fn yellow(s: &str) -> String {
+    format!("\x1b[33m{}\x1b[0m", s)
+}
+fn red(s: &str) -> String {
+    format!("\x1b[31m{}\x1b[0m", s)
+}
+
+/// Can `postject` be resolved via `npx`?
+fn postject_resolvable() -> bool {
+    let npx = if cfg!(windows) { "npx.cmd" } else { "npx" };
+    Command::new(npx)
+        .args(["--no-install", "postject", "--version"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
@@ -0,0 +1,146 @@
+//! Reproducible-build lockfile (`npack.lock`).
+//!
+//! Inspired by how cargo pins dependencies in a generated lockfile, this
+//! records the exact inputs a build resolved — the git commit behind a
+//! `source`, the concrete Node version that was downloaded, the hash of that
+//! binary, and content hashes of every declared asset and script. On a later
+//! build the current inputs are checked against the lock so drift (a moved git
+//! ref, a changed node binary, a modified asset) is surfaced rather than
+//! silently baked into a new artifact.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::NpackConfig;
+
+/// A snapshot of the concrete inputs that produced a build.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NpackLock {
+    /// Commit SHA resolved from a git `source`, if the source is a checkout.
+    pub source_commit: Option<String>,
+
+    /// The concrete Node.js version that was downloaded (e.g. `24.12.0`).
+    pub node_version: String,
+
+    /// SHA-256 of the downloaded Node binary.
+    pub node_binary_sha256: String,
+
+    /// Declared asset path → content hash (sorted for a stable file).
+    pub assets: BTreeMap<String, String>,
+
+    /// Declared script path → content hash (sorted for a stable file).
+    pub scripts: BTreeMap<String, String>,
+}
+
+impl NpackLock {
+    /// Capture the current build inputs into a lock.
+    pub fn generate(
+        config: &NpackConfig,
+        app_path: &Path,
+        node_version: &str,
+        node_binary: &Path,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(NpackLock {
+            source_commit: git_commit(app_path),
+            node_version: node_version.to_string(),
+            node_binary_sha256: hash_file(node_binary)?,
+            assets: hash_all(app_path, &config.get_assets()),
+            scripts: hash_all(app_path, &config.get_scripts()),
+        })
+    }
+
+    /// Load an existing lock from disk.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Write the lock to disk.
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Compare a previous lock against the current inputs, returning a list of
+    /// human-readable drift descriptions (empty when the build is reproducible).
+    pub fn diff(&self, current: &NpackLock) -> Vec<String> {
+        let mut drift = Vec::new();
+
+        if self.source_commit != current.source_commit {
+            drift.push(format!(
+                "git commit drifted: {:?} → {:?}",
+                self.source_commit, current.source_commit
+            ));
+        }
+        if self.node_version != current.node_version {
+            drift.push(format!(
+                "node version changed: {} → {}",
+                self.node_version, current.node_version
+            ));
+        }
+        if self.node_binary_sha256 != current.node_binary_sha256 {
+            drift.push("node binary hash changed".to_string());
+        }
+
+        for (path, hash) in &self.assets {
+            match current.assets.get(path) {
+                Some(h) if h != hash => drift.push(format!("asset modified: {}", path)),
+                None => drift.push(format!("asset removed: {}", path)),
+                _ => {}
+            }
+        }
+        for (path, hash) in &self.scripts {
+            match current.scripts.get(path) {
+                Some(h) if h != hash => drift.push(format!("script modified: {}", path)),
+                None => drift.push(format!("script removed: {}", path)),
+                _ => {}
+            }
+        }
+
+        drift
+    }
+}
+
+/// Resolve the current commit SHA of a git checkout, or `None` if not a repo.
+fn git_commit(app_path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .current_dir(app_path)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Hash every file matched by each path or glob (resolved relative to
+/// `app_path`) into a sorted map keyed by the file's path relative to `app_path`.
+fn hash_all(app_path: &Path, patterns: &[String]) -> BTreeMap<String, String> {
+    let mut map = BTreeMap::new();
+    for pattern in patterns {
+        let full = app_path.join(pattern);
+        let Ok(paths) = glob::glob(&full.to_string_lossy()) else {
+            continue;
+        };
+        for path in paths.flatten() {
+            if let Ok(hash) = hash_file(&path) {
+                let rel = path.strip_prefix(app_path).unwrap_or(&path);
+                map.insert(rel.to_string_lossy().into_owned(), hash);
+            }
+        }
+    }
+    map
+}
+
+/// SHA-256 of a file's contents, as a lowercase hex string.
+fn hash_file(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(path)?;
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -1,17 +1,36 @@
 mod config;
-use clap::Parser;
+mod info;
+mod lock;
+mod npm;
+mod sea;
+mod storage;
+use clap::{Parser, Subcommand};
 use config::NpackConfig;
 use serde::{Deserialize, Serialize};
 use std::fs::{self};
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use tokio::process::Command as TokioCommand;
 
 #[derive(Parser, Debug, Clone)]
 #[command(name = "npack")]
 #[command(version = "0.0.1")]
 #[command(about = "Package Node.js apps into standalone executables", long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    #[command(flatten)]
+    pub build: Args,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Commands {
+    /// Print a diagnostic preflight report about the toolchain and target project
+    Info(Args),
+}
+
+#[derive(Parser, Debug, Clone)]
 pub struct Args {
     pub source: Option<String>,
     #[arg(long)]
@@ -29,6 +48,10 @@ pub struct Args {
     #[arg(long)]
     pub run_postinstall: bool,
     #[arg(long)]
+    pub no_cache: bool,
+    #[arg(long)]
+    pub frozen: bool,
+    #[arg(long)]
     pub db_connection: Option<String>,
     #[arg(long)]
     pub s3_key: Option<String>,
@@ -58,35 +81,130 @@ struct SEAConfig {
     disable_experimental_sea_warning: bool,
 }
 
-const NODE_SEA_FUSE: &str = "NODE_SEA_FUSE_fce680ab2cc467b6e072b8b5df1996b2";
+pub(crate) const NODE_SEA_FUSE: &str = "NODE_SEA_FUSE_fce680ab2cc467b6e072b8b5df1996b2";
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut args = Args::parse();
-    args.merge_with_env();
+/// Normalize a `std::env::consts::OS` value to npack's short OS name.
+fn os_label(os: &str) -> &str {
+    match os {
+        "macos" | "darwin" => "macos",
+        other => other,
+    }
+}
+
+/// Normalize a `std::env::consts::ARCH` value to npack's short arch name.
+fn arch_label(arch: &str) -> &str {
+    match arch {
+        "aarch64" | "arm64" => "arm64",
+        "x86_64" | "x64" => "x64",
+        other => other,
+    }
+}
+
+/// Node.js dist folder component for an (os, arch) pair,
+/// e.g. `("macos", "arm64") -> "darwin-arm64"`.
+fn node_dist_arch(os: &str, arch: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let os_part = match os {
+        "linux" => "linux",
+        "macos" | "darwin" => "darwin",
+        "windows" => "win",
+        _ => return Err(format!("Unsupported OS: {}", os).into()),
+    };
+    let arch_part = match arch {
+        "x64" | "arm64" => arch,
+        _ => return Err(format!("Unsupported architecture: {}", arch).into()),
+    };
+    Ok(format!("{}-{}", os_part, arch_part))
+}
+
+/// Output executable file name for an (os, arch) pair, e.g. `app-macos-arm64`.
+fn output_name(os: &str, arch: &str) -> String {
+    let ext = if os == "windows" { ".exe" } else { "" };
+    format!("app-{}-{}{}", os, arch, ext)
+}
+
+/// Expand a `--platform` spec into the concrete list of `(os, arch)` targets.
+///
+/// Accepts `host` (the current machine), `all` (the full os × arch matrix),
+/// a bare OS name like `linux` (defaulting to the host arch), or a full
+/// `os-arch` tuple like `macos-arm64`.
+fn resolve_targets(platform: &str) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let host_arch = arch_label(std::env::consts::ARCH).to_string();
+
+    match platform {
+        "host" => Ok(vec![(
+            os_label(std::env::consts::OS).to_string(),
+            host_arch,
+        )]),
+        "all" => {
+            let mut targets = Vec::new();
+            for os in ["linux", "macos", "windows"] {
+                for arch in ["x64", "arm64"] {
+                    targets.push((os.to_string(), arch.to_string()));
+                }
+            }
+            Ok(targets)
+        }
+        other => {
+            if let Some((os, arch)) = other.split_once('-') {
+                Ok(vec![(os.to_string(), arch.to_string())])
+            } else {
+                Ok(vec![(other.to_string(), host_arch)])
+            }
+        }
+    }
+}
+
+/// Resolve the effective config by layering sources: discovered/global/project
+/// config first, then CLI args as the top override layer.
+fn resolve_config(args: &Args) -> Result<NpackConfig, Box<dyn std::error::Error>> {
+    use config::Merge;
 
-    let mut config = if args.config {
-        NpackConfig::find_in_cwd().unwrap_or_default()
-    } else if let Some(config_path) = &args.config_file {
+    let mut config = if let Some(config_path) = &args.config_file {
         NpackConfig::from_file(config_path)?
+    } else if args.config {
+        NpackConfig::discover()
     } else {
         NpackConfig::default()
     };
 
-    config.merge_with_args(&args);
-    config.validate()?;
+    config.merge(NpackConfig::from(args));
+    config.resolve();
+    Ok(config)
+}
 
-    println!("📦 npack v{}\n", env!("CARGO_PKG_VERSION"));
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Commands::Info(mut args)) => {
+            args.merge_with_env();
+            let config = resolve_config(&args)?;
+            if let Err(e) = info::run(&config).await {
+                eprintln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        None => {
+            let mut args = cli.build;
+            args.merge_with_env();
+            let mut config = resolve_config(&args)?;
+            config.validate()?;
 
-    let env_vars = config.get_env_vars();
-    for (key, value) in &env_vars {
-        std::env::set_var(key, value);
-    }
+            println!("📦 npack v{}\n", env!("CARGO_PKG_VERSION"));
 
-    if let Err(e) = run(config).await {
-        eprintln!("❌ Error: {}", e);
-        std::process::exit(1);
+            let env_vars = config.get_env_vars();
+            for (key, value) in &env_vars {
+                std::env::set_var(key, value);
+            }
+
+            if let Err(e) = run(config).await {
+                eprintln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
     }
+
     Ok(())
 }
 
@@ -99,21 +217,27 @@ async fn run(config: NpackConfig) -> Result<(), Box<dyn std::error::Error>> {
     fs::create_dir_all(&output)
         .map_err(|e| format!("Failed to create output directory {:?}: {}", output, e))?;
 
-    let target_platform = if platform == "host" {
-        std::env::consts::OS
-    } else {
-        platform.as_str()
-    };
+    // The node binary used to *generate* the SEA blob runs locally, so it must
+    // always match the host os/arch regardless of the requested targets.
+    let node_arch = node_dist_arch(
+        os_label(std::env::consts::OS),
+        arch_label(std::env::consts::ARCH),
+    )?;
 
-    let (node_arch, _) = match target_platform {
-        "linux" => ("linux-x64", "app-linux"),
-        "macos" | "darwin" => ("darwin-x64", "app-macos"),
-        "windows" => ("win-x64", "app-windows.exe"),
-        _ => return Err(format!("Unsupported platform: {}", target_platform).into()),
+    let cache_dir = if config.cache_enabled() {
+        config.get_cache_dir()
+    } else {
+        None
     };
 
     let node_binary_path = output.join("node-binary");
-    download_node_binary(&node_version, node_arch, &node_binary_path).await?;
+    download_node_binary(
+        &node_version,
+        &node_arch,
+        &node_binary_path,
+        cache_dir.as_deref(),
+    )
+    .await?;
 
     #[cfg(unix)]
     {
@@ -123,7 +247,10 @@ async fn run(config: NpackConfig) -> Result<(), Box<dyn std::error::Error>> {
         fs::set_permissions(&node_binary_path, perms)?;
     }
 
-    let app_path = if source.starts_with("http") || source.starts_with("git@") {
+    let app_path = if source.starts_with("s3://") {
+        println!("☁️  Pulling source from S3...");
+        storage::s3::pull_source(&source, &config, &output).await?
+    } else if source.starts_with("http") || source.starts_with("git@") {
         println!("🔄 Cloning repository...");
         clone_repository(&source, &output)?
     } else {
@@ -153,7 +280,7 @@ async fn run(config: NpackConfig) -> Result<(), Box<dyn std::error::Error>> {
     // create_runtime_loader(&loader_path, &encryption_key)?;
 
     println!("\n📦 Creating Node.js SEA...");
-    let sea_blob = create_sea(&bundle_path, &output, &node_binary_path)?;
+    let sea_blob = create_sea(&bundle_path, &app_path, &output, &node_binary_path, &config)?;
 
     println!("\n🎯 Creating platform executables...");
     create_executables(
@@ -162,6 +289,7 @@ async fn run(config: NpackConfig) -> Result<(), Box<dyn std::error::Error>> {
         &output,
         &node_version,
         &node_binary_path,
+        cache_dir.as_deref(),
     )
     .await?;
 
@@ -169,6 +297,79 @@ async fn run(config: NpackConfig) -> Result<(), Box<dyn std::error::Error>> {
     // println!("\n📦 Copying assets...");
     // copy_assets_to_dist(&output, &bundle_path, &enc_path)?;
 
+    // Emit per-target npm wrapper packages when npm output is configured.
+    if config.get_npm_name().is_some() && !config.get_targets().is_empty() {
+        println!("\n📦 Emitting npm wrapper packages...");
+        for tuple in config.get_targets() {
+            let (os, arch) = tuple
+                .split_once('-')
+                .ok_or_else(|| format!("Invalid target tuple: {}", tuple))?;
+            build_for_platform(
+                os,
+                arch,
+                &sea_blob,
+                &output,
+                &node_version,
+                &node_binary_path,
+                cache_dir.as_deref(),
+            )
+            .await?;
+        }
+        npm::emit_packages(&output, &config)?;
+        if config.should_publish() {
+            println!("\n🚀 Publishing npm packages...");
+            npm::publish(&output, &config)?;
+        }
+    }
+
+    // Record (and verify against) the reproducible-build lockfile. It pins the
+    // build's inputs and is meant to be committed, so it lives at the project
+    // root next to the source — not under the (throwaway, gitignored) output
+    // directory.
+    println!("\n🔒 Updating npack.lock...");
+    let full_version = resolve_node_version(&node_version).await?;
+    let lock_path = app_path.join("npack.lock");
+    let current_lock = lock::NpackLock::generate(&config, &app_path, &full_version, &node_binary_path)?;
+    if lock_path.exists() {
+        match lock::NpackLock::load(&lock_path) {
+            Ok(previous) => {
+                let drift = previous.diff(&current_lock);
+                if drift.is_empty() {
+                    println!("   ✓ Inputs match npack.lock");
+                } else if config.is_frozen() {
+                    // --frozen: drift is a hard error, like `cargo --frozen`.
+                    let mut message = String::from("Build inputs drifted from npack.lock:");
+                    for entry in &drift {
+                        message.push_str(&format!("\n   - {}", entry));
+                    }
+                    return Err(message.into());
+                } else {
+                    for entry in &drift {
+                        eprintln!("   ⚠️ {}", entry);
+                    }
+                }
+            }
+            Err(e) => eprintln!("   ⚠️ Could not read npack.lock: {}", e),
+        }
+    }
+    current_lock.write(&lock_path)?;
+    println!("   ✓ Wrote {:?}", lock_path);
+
+    // Push the finished host artifact to S3 when an upload bucket is set.
+    if config.get_s3_bucket().is_some() {
+        println!("\n☁️  Uploading artifact to S3...");
+        let artifact_name = output_name(
+            os_label(std::env::consts::OS),
+            arch_label(std::env::consts::ARCH),
+        );
+        let artifact = output.join(&artifact_name);
+        if artifact.exists() {
+            storage::s3::push_artifact(&artifact, &artifact_name, &config).await?;
+        } else {
+            eprintln!("   ⚠️ Host artifact {:?} not found, skipping upload", artifact);
+        }
+    }
+
     println!("\n✅ Done! Executables:");
     list_executables(&output)?;
 
@@ -201,19 +402,76 @@ fn copy_assets_to_dist(
 
 fn create_sea(
     bundle_path: &Path, // ✅ Напрямую bundle.js
+    app_path: &Path,
     output: &Path,
     node_binary: &Path,
+    npack_config: &NpackConfig,
 ) -> Result<PathBuf, Box<dyn std::error::Error>> {
     let sea_config_path = output.join("sea-config.json");
     let sea_blob_path = output.join("sea-prep.blob");
 
-    let config = serde_json::json!({
+    let mut sea_config = serde_json::json!({
         "main": bundle_path.to_string_lossy(),  // ✅ bundle.js
         "output": sea_blob_path.to_string_lossy(),
         "disableExperimentalSEAWarning": true,
     });
 
-    fs::write(&sea_config_path, serde_json::to_string_pretty(&config)?)?;
+    // Embed declared assets (key → glob), resolved relative to the app. A
+    // pattern matching a single file keeps its declared key; one matching
+    // several files fans out, each keyed by its path relative to `app_path` so
+    // it stays addressable via `sea.getAsset`.
+    let assets = npack_config.get_sea_assets();
+    if !assets.is_empty() {
+        let mut resolved = serde_json::Map::new();
+        for (key, pattern) in &assets {
+            let glob_pattern = app_path.join(pattern);
+            let mut matches = Vec::new();
+            for entry in glob::glob(&glob_pattern.to_string_lossy())? {
+                let path = entry?;
+                if path.is_file() {
+                    matches.push(path);
+                }
+            }
+            if matches.is_empty() {
+                return Err(format!("Asset {:?} matched no files: {:?}", key, glob_pattern).into());
+            }
+            if matches.len() == 1 {
+                resolved.insert(
+                    key.clone(),
+                    serde_json::Value::String(matches[0].to_string_lossy().into_owned()),
+                );
+            } else {
+                for path in &matches {
+                    let asset_key = path
+                        .strip_prefix(app_path)
+                        .unwrap_or(path)
+                        .to_string_lossy()
+                        .into_owned();
+                    resolved.insert(
+                        asset_key,
+                        serde_json::Value::String(path.to_string_lossy().into_owned()),
+                    );
+                }
+            }
+        }
+        println!("   ✓ Embedding {} asset(s)", resolved.len());
+        sea_config["assets"] = serde_json::Value::Object(resolved);
+    }
+
+    // A startup snapshot and the code cache are mutually exclusive in Node's
+    // SEA: with `useSnapshot` the snapshot is built from `main` at
+    // `--experimental-sea-config` time, so point `main` at the snapshot entry
+    // and leave the code cache off. Otherwise enable the code cache.
+    if let Some(entry) = npack_config.get_startup_snapshot() {
+        let snapshot_entry = app_path.join(&entry);
+        sea_config["useSnapshot"] = serde_json::Value::Bool(true);
+        sea_config["main"] = serde_json::Value::String(snapshot_entry.to_string_lossy().into_owned());
+        println!("   Using startup snapshot entry {:?}", snapshot_entry);
+    } else {
+        sea_config["useCodeCache"] = serde_json::Value::Bool(true);
+    }
+
+    fs::write(&sea_config_path, serde_json::to_string_pretty(&sea_config)?)?;
 
     let output = Command::new(node_binary)
         .arg("--experimental-sea-config")
@@ -480,41 +738,41 @@ async fn create_executables(
     output: &Path,
     node_version: &str,
     node_binary_path: &Path,
+    cache_dir: Option<&Path>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let platforms = match platform {
-        "all" => vec!["linux", "macos", "windows"],
-        "host" => {
-            let os = std::env::consts::OS;
-            vec![if os == "darwin" { "macos" } else { os }]
-        }
-        _ => vec![platform],
-    };
-
-    for p in platforms {
-        build_for_platform(p, sea_blob, output, node_version, node_binary_path).await?;
+    let targets = resolve_targets(platform)?;
+
+    for (os, arch) in targets {
+        build_for_platform(
+            &os,
+            &arch,
+            sea_blob,
+            output,
+            node_version,
+            node_binary_path,
+            cache_dir,
+        )
+        .await?;
     }
     Ok(())
 }
 
 async fn build_for_platform(
-    platform: &str,
+    os: &str,
+    arch: &str,
     sea_blob: &Path,
     output: &Path,
     node_version: &str,
     _node_binary_path: &Path,
+    cache_dir: Option<&Path>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("   Building for {}...", platform);
-
-    let (output_name, node_arch) = match platform {
-        "linux" => ("app-linux", "linux-x64"),
-        "macos" | "darwin" => ("app-macos", "darwin-x64"),
-        "windows" => ("app-windows.exe", "win-x64"),
-        _ => return Err(format!("Unsupported platform: {}", platform).into()),
-    };
+    println!("   Building for {}-{}...", os, arch);
 
-    let exe_path = output.join(output_name);
+    let node_arch = node_dist_arch(os, arch)?;
+    let exe_name = output_name(os, arch);
+    let exe_path = output.join(&exe_name);
 
-    download_node_binary(node_version, node_arch, &exe_path).await?;
+    download_node_binary(node_version, &node_arch, &exe_path, cache_dir).await?;
 
     #[cfg(unix)]
     {
@@ -524,16 +782,16 @@ async fn build_for_platform(
         fs::set_permissions(&exe_path, perms)?;
     }
 
-    if platform == "macos" || platform == "darwin" {
+    if os == "macos" || os == "darwin" {
         let _ = Command::new("codesign")
             .arg("--remove-signature")
             .arg(&exe_path)
             .output();
     }
 
-    inject_sea_blob(&exe_path, sea_blob, platform).await?;
+    inject_sea_blob(&exe_path, sea_blob, os)?;
 
-    if platform == "macos" || platform == "darwin" {
+    if os == "macos" || os == "darwin" {
         let _ = Command::new("codesign")
             .arg("--sign")
             .arg("-")
@@ -541,50 +799,26 @@ async fn build_for_platform(
             .output();
     }
 
-    println!("      ✓ {}", output_name);
+    println!("      ✓ {}", exe_name);
     Ok(())
 }
 
-async fn inject_sea_blob(
+/// Inject the SEA blob into `exe_path` natively, replacing the former
+/// `npx postject` shell-out with an in-process rewrite (see [`sea`]).
+fn inject_sea_blob(
     exe_path: &Path,
     sea_blob: &Path,
-    platform: &str,
+    os: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let npx_cmd = if cfg!(windows) { "npx.cmd" } else { "npx" };
-
-    let mut cmd = TokioCommand::new(npx_cmd);
-    cmd.arg("postject")
-        .arg(exe_path)
-        .arg("NODE_SEA_BLOB")
-        .arg(sea_blob)
-        .arg("--sentinel-fuse")
-        .arg(NODE_SEA_FUSE);
-
-    if platform == "macos" || platform == "darwin" {
-        cmd.arg("--macho-segment-name").arg("NODE_SEA");
-    }
-
-    println!("   Running: {} postject {:?}", npx_cmd, exe_path);
-
-    let output = cmd.output().await?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        return Err(format!(
-            "Failed to inject SEA blob.\nstdout: {}\nstderr: {}",
-            stdout, stderr
-        )
-        .into());
-    }
-
-    Ok(())
+    println!("   Injecting SEA blob into {:?}", exe_path);
+    sea::inject_blob(exe_path, sea_blob, os)
 }
 
 async fn download_node_binary(
     version: &str,
     arch: &str,
     dest: &Path,
+    cache_dir: Option<&Path>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if let Some(parent) = dest.parent() {
         fs::create_dir_all(parent)?;
@@ -594,6 +828,23 @@ async fn download_node_binary(
 
     let is_windows = arch.starts_with("win");
 
+    // Cache hit: the extracted binary lives at <cache>/<full_version>/<arch>/node
+    let cached = cache_dir.map(|root| {
+        root.join(&full_version).join(arch).join(if is_windows {
+            "node.exe"
+        } else {
+            "node"
+        })
+    });
+
+    if let Some(cached) = &cached {
+        if cached.exists() {
+            fs::copy(cached, dest)?;
+            println!("   ✓ Node.js binary from cache: {:?}", cached);
+            return Ok(());
+        }
+    }
+
     let archive_name = if is_windows {
         format!("node-v{}-{}.zip", full_version, arch)
     } else {
@@ -615,6 +866,8 @@ async fn download_node_binary(
 
     let body = response.bytes().await?;
 
+    verify_checksum(&full_version, &archive_name, &body).await?;
+
     let temp_dir = tempfile::tempdir()?;
     let archive_path = temp_dir.path().join(&archive_name);
     fs::write(&archive_path, &body)?;
@@ -627,10 +880,74 @@ async fn download_node_binary(
         extract_node_from_tar_gz(&archive_path, &folder_name, dest)?;
     }
 
+    // Populate the cache so later targets (and future runs) skip the network.
+    if let Some(cached) = &cached {
+        if let Some(parent) = cached.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(dest, cached)?;
+    }
+
     println!("   ✓ Node.js binary saved to: {:?}", dest);
     Ok(())
 }
 
+/// Verify the downloaded archive against the official `SHASUMS256.txt` manifest.
+///
+/// nodejs.org publishes a signed checksum manifest next to every release; each
+/// line is `<hex-sha256>  <filename>`. We look up the entry matching
+/// `archive_name`, hash the bytes we actually received and bail out on any
+/// mismatch so a corrupted or tampered download never reaches the SEA step.
+async fn verify_checksum(
+    full_version: &str,
+    archive_name: &str,
+    body: &[u8],
+) -> Result<(), Box<dyn std::error::Error>> {
+    use sha2::{Digest, Sha256};
+
+    let url = format!("https://nodejs.org/dist/v{}/SHASUMS256.txt", full_version);
+    let manifest = reqwest::get(&url).await?;
+    if !manifest.status().is_success() {
+        return Err(format!(
+            "Failed to fetch checksum manifest {}: {}",
+            url,
+            manifest.status()
+        )
+        .into());
+    }
+    let manifest = manifest.text().await?;
+
+    let expected = manifest
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let name = parts.next()?;
+            if name == archive_name {
+                Some(hash)
+            } else {
+                None
+            }
+        })
+        .next()
+        .ok_or_else(|| format!("{} not found in SHASUMS256.txt", archive_name))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(body);
+    let actual = format!("{:x}", hasher.finalize());
+
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            archive_name, expected, actual
+        )
+        .into());
+    }
+
+    println!("   ✓ Verified SHA-256 checksum");
+    Ok(())
+}
+
 fn extract_node_from_zip(
     archive_path: &Path,
     folder_name: &str,
@@ -0,0 +1,193 @@
+//! Emit per-target npm wrapper packages.
+//!
+//! Following the wrap-for-npm pattern (used by esbuild, swc and friends), each
+//! build target becomes its own `@org/<name>-bin-<tuple>` sub-package that
+//! declares `os`/`cpu` constraints and ships only that platform's binary. A
+//! root package lists them all as `optionalDependencies` so `npm install`
+//! pulls exactly the binary matching the host, and a generated `postinstall.js`
+//! / bin shim resolves and re-execs it at runtime.
+
+use std::fs;
+use std::path::Path;
+
+use crate::config::NpackConfig;
+use crate::output_name;
+
+/// A build target expressed in both npack and npm vocabularies.
+struct Target {
+    /// npm os token (`linux`, `darwin`, `win32`)
+    npm_os: &'static str,
+    /// npm cpu token (`x64`, `arm64`)
+    npm_cpu: &'static str,
+    /// npack os label (`linux`, `macos`, `windows`)
+    os: String,
+    /// npack arch label (`x64`, `arm64`)
+    arch: String,
+}
+
+impl Target {
+    /// The package-name slug, e.g. `linux-x64` or `win32-arm64`.
+    fn slug(&self) -> String {
+        format!("{}-{}", self.npm_os, self.npm_cpu)
+    }
+}
+
+/// Parse an npack target tuple (`macos-arm64`) into a [`Target`].
+fn parse_target(tuple: &str) -> Result<Target, Box<dyn std::error::Error>> {
+    let (os, arch) = tuple
+        .split_once('-')
+        .ok_or_else(|| format!("Invalid target tuple: {}", tuple))?;
+    let npm_os = match os {
+        "linux" => "linux",
+        "macos" => "darwin",
+        "windows" => "win32",
+        _ => return Err(format!("Unknown OS in target: {}", tuple).into()),
+    };
+    let npm_cpu = match arch {
+        "x64" => "x64",
+        "arm64" => "arm64",
+        _ => return Err(format!("Unknown arch in target: {}", tuple).into()),
+    };
+    Ok(Target {
+        npm_os,
+        npm_cpu,
+        os: os.to_string(),
+        arch: arch.to_string(),
+    })
+}
+
+/// Generate the wrapper packages under `<output>/npm` for every configured
+/// target, copying each already-built binary into its sub-package.
+pub fn emit_packages(
+    output: &Path,
+    config: &NpackConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let org = config.get_npm_org().ok_or("npm_org is required for npm output")?;
+    let name = config.get_npm_name().ok_or("npm_name is required for npm output")?;
+    let version = "0.0.0";
+
+    let npm_dir = output.join("npm");
+    fs::create_dir_all(&npm_dir)?;
+
+    let mut optional_deps = serde_json::Map::new();
+
+    for tuple in config.get_targets() {
+        let target = parse_target(&tuple)?;
+        let slug = target.slug();
+        let pkg_name = format!("{}/{}-bin-{}", org, name, slug);
+
+        let binary_name = output_name(&target.os, &target.arch);
+        let built = output.join(&binary_name);
+        if !built.exists() {
+            return Err(format!("Built binary {:?} not found for target {}", built, tuple).into());
+        }
+
+        let pkg_dir = npm_dir.join(format!("{}-bin-{}", name, slug));
+        fs::create_dir_all(&pkg_dir)?;
+        fs::copy(&built, pkg_dir.join(&binary_name))?;
+
+        let pkg_json = serde_json::json!({
+            "name": pkg_name,
+            "version": version,
+            "os": [target.npm_os],
+            "cpu": [target.npm_cpu],
+            "files": [binary_name],
+        });
+        fs::write(
+            pkg_dir.join("package.json"),
+            serde_json::to_string_pretty(&pkg_json)?,
+        )?;
+
+        optional_deps.insert(
+            pkg_name,
+            serde_json::Value::String(version.to_string()),
+        );
+        println!("   ✓ {}-bin-{}", name, slug);
+    }
+
+    // Root package ties the per-target packages together and re-execs the
+    // correct one via a postinstall-installed bin shim.
+    let bin_name = name.clone();
+    let root_json = serde_json::json!({
+        "name": format!("{}/{}", org, name),
+        "version": version,
+        "bin": { &bin_name: "postinstall.js" },
+        "scripts": { "postinstall": "node postinstall.js --check" },
+        "optionalDependencies": serde_json::Value::Object(optional_deps),
+    });
+    fs::write(
+        npm_dir.join("package.json"),
+        serde_json::to_string_pretty(&root_json)?,
+    )?;
+    fs::write(npm_dir.join("postinstall.js"), shim_source(&org, &name))?;
+
+    println!("   ✓ Root package {}/{}", org, name);
+    Ok(())
+}
+
+/// Run `npm publish` for each generated sub-package and the root package.
+pub fn publish(output: &Path, config: &NpackConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let npm_cmd = if cfg!(windows) { "npm.cmd" } else { "npm" };
+    let name = config.get_npm_name().ok_or("npm_name is required to publish")?;
+    let npm_dir = output.join("npm");
+
+    for tuple in config.get_targets() {
+        let target = parse_target(&tuple)?;
+        let pkg_dir = npm_dir.join(format!("{}-bin-{}", name, target.slug()));
+        publish_dir(npm_cmd, &pkg_dir)?;
+    }
+    publish_dir(npm_cmd, &npm_dir)?;
+
+    Ok(())
+}
+
+/// `npm publish --access public` within a single package directory.
+fn publish_dir(npm_cmd: &str, dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let status = std::process::Command::new(npm_cmd)
+        .current_dir(dir)
+        .args(["publish", "--access", "public"])
+        .status()?;
+    if !status.success() {
+        return Err(format!("npm publish failed in {:?}", dir).into());
+    }
+    println!("   ✓ Published {:?}", dir);
+    Ok(())
+}
+
+/// The bin shim that resolves the platform package and re-execs its binary.
+fn shim_source(org: &str, name: &str) -> String {
+    // process.platform / process.arch already use the npm os/cpu vocabulary.
+    format!(
+        r#"#!/usr/bin/env node
+// Auto-generated by npack. Resolves the platform-specific binary and re-execs it.
+const {{ spawnSync }} = require("child_process");
+const path = require("path");
+
+const pkg = `{org}/{name}-bin-${{process.platform}}-${{process.arch}}`;
+const ext = process.platform === "win32" ? ".exe" : "";
+const binary = `app-${{mapOs(process.platform)}}-${{process.arch}}${{ext}}`;
+
+function mapOs(p) {{
+  return p === "darwin" ? "macos" : p === "win32" ? "windows" : p;
+}}
+
+let dir;
+try {{
+  dir = path.dirname(require.resolve(`${{pkg}}/package.json`));
+}} catch (e) {{
+  console.error(`npack: no prebuilt binary for ${{process.platform}}-${{process.arch}}`);
+  process.exit(1);
+}}
+
+const bin = path.join(dir, binary);
+if (process.argv.includes("--check")) {{
+  process.exit(0);
+}}
+
+const result = spawnSync(bin, process.argv.slice(2), {{ stdio: "inherit" }});
+process.exit(result.status === null ? 1 : result.status);
+"#,
+        org = org,
+        name = name,
+    )
+}
@@ -0,0 +1,510 @@
+//! Native Single-Executable-Application blob injection.
+//!
+//! Removes the `npx postject` runtime dependency — the slowest, flakiest step
+//! in the pipeline — by performing the injection directly in Rust. We use the
+//! `object` crate to parse container layout and then rewrite the bytes,
+//! performing the same two operations postject does:
+//!
+//! 1. **Blow the fuse** — locate the [`NODE_SEA_FUSE`](crate::NODE_SEA_FUSE)
+//!    sentinel and flip the byte immediately after its `:` from `0` to `1`.
+//! 2. **Add the blob as a named resource** — a `NODE_SEA_BLOB` section for ELF,
+//!    a section named `NODE_SEA` for PE (PE section names hold at most 8 bytes),
+//!    and a `NODE_SEA_BLOB` section inside a `NODE_SEA` segment for Mach-O
+//!    (matching the previous `--macho-segment-name` argument).
+//!
+//! The macOS code-signing dance still wraps this path in `build_for_platform`
+//! (`codesign --remove-signature` before, `--sign -` after); because the
+//! signature is stripped before injection, Mach-O rewriting does not need to
+//! preserve an `LC_CODE_SIGNATURE`.
+
+use object::read::{File, Object};
+use std::fs;
+use std::path::Path;
+
+use crate::NODE_SEA_FUSE;
+
+const BLOB_RESOURCE_NAME: &str = "NODE_SEA_BLOB";
+const MACHO_SEGMENT_NAME: &str = "NODE_SEA";
+
+/// Inject `blob` into the executable at `exe_path`, dispatching on container
+/// format detected by `object`.
+pub fn inject_blob(exe_path: &Path, blob: &Path, os: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut bytes = fs::read(exe_path)?;
+    let blob = fs::read(blob)?;
+
+    blow_fuse(&mut bytes)?;
+
+    let format = File::parse(&*bytes)?.format();
+    let patched = match format {
+        object::BinaryFormat::Elf => inject_elf(bytes, &blob)?,
+        object::BinaryFormat::MachO => inject_macho(bytes, &blob)?,
+        object::BinaryFormat::Pe => inject_pe(bytes, &blob)?,
+        other => return Err(format!("Unsupported executable format for {}: {:?}", os, other).into()),
+    };
+
+    fs::write(exe_path, patched)?;
+    Ok(())
+}
+
+/// Flip the SEA fuse from `0` to `1` in place.
+fn blow_fuse(bytes: &mut [u8]) -> Result<(), Box<dyn std::error::Error>> {
+    let fuse = NODE_SEA_FUSE.as_bytes();
+    let start = find_subslice(bytes, fuse).ok_or("SEA fuse sentinel not found in binary")?;
+
+    // The sentinel is stored as `NODE_SEA_FUSE_<hash>:0`; flip the digit after
+    // the colon that follows the sentinel.
+    let colon = bytes[start..]
+        .iter()
+        .position(|&b| b == b':')
+        .ok_or("Malformed SEA fuse: missing ':' separator")?;
+    let fuse_byte = start + colon + 1;
+    if fuse_byte >= bytes.len() {
+        return Err("Malformed SEA fuse: truncated".into());
+    }
+    bytes[fuse_byte] = b'1';
+    Ok(())
+}
+
+/// Find the first occurrence of `needle` within `haystack`.
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+// ---------------------------------------------------------------------------
+// ELF
+// ---------------------------------------------------------------------------
+
+/// Append a `NODE_SEA_BLOB` PROGBITS section to a 64-bit ELF.
+///
+/// The blob, a grown `.shstrtab` (carrying the new section name) and a
+/// relocated section-header table are all appended at EOF; the ELF header's
+/// `e_shoff`/`e_shnum` are patched to point at the new table. Existing content
+/// keeps its offsets, so no program header rewrite is needed.
+fn inject_elf(mut bytes: Vec<u8>, blob: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    const SHT_PROGBITS: u32 = 1;
+    const SHDR_SIZE: usize = 64;
+
+    let e_shoff = read_u64(&bytes, 0x28) as usize;
+    let e_shentsize = read_u16(&bytes, 0x3a) as usize;
+    let e_shnum = read_u16(&bytes, 0x3c) as usize;
+    let e_shstrndx = read_u16(&bytes, 0x3e) as usize;
+
+    if e_shentsize != SHDR_SIZE {
+        return Err("Unsupported ELF (only 64-bit section headers handled)".into());
+    }
+
+    // Locate the existing .shstrtab so we can grow it with the new name.
+    let shstr_hdr = e_shoff + e_shstrndx * SHDR_SIZE;
+    let shstr_off = read_u64(&bytes, shstr_hdr + 0x18) as usize;
+    let shstr_size = read_u64(&bytes, shstr_hdr + 0x20) as usize;
+
+    let mut new_shstr = bytes[shstr_off..shstr_off + shstr_size].to_vec();
+    let name_index = new_shstr.len() as u32;
+    new_shstr.extend_from_slice(BLOB_RESOURCE_NAME.as_bytes());
+    new_shstr.push(0);
+
+    // Copy the existing section headers; repoint .shstrtab at its new home.
+    let mut headers = bytes[e_shoff..e_shoff + e_shnum * SHDR_SIZE].to_vec();
+
+    // Append blob bytes.
+    let blob_off = bytes.len();
+    bytes.extend_from_slice(blob);
+
+    // Append the grown string table.
+    let new_shstr_off = bytes.len();
+    bytes.extend_from_slice(&new_shstr);
+
+    // Point the .shstrtab header at the new table.
+    let shstr_entry = e_shstrndx * SHDR_SIZE;
+    write_u64(&mut headers, shstr_entry + 0x18, new_shstr_off as u64);
+    write_u64(&mut headers, shstr_entry + 0x20, new_shstr.len() as u64);
+
+    // Build the new section header for the blob.
+    let mut new_hdr = vec![0u8; SHDR_SIZE];
+    write_u32(&mut new_hdr, 0x00, name_index); // sh_name
+    write_u32(&mut new_hdr, 0x04, SHT_PROGBITS); // sh_type
+    write_u64(&mut new_hdr, 0x18, blob_off as u64); // sh_offset
+    write_u64(&mut new_hdr, 0x20, blob.len() as u64); // sh_size
+    write_u64(&mut new_hdr, 0x30, 1); // sh_addralign
+    headers.extend_from_slice(&new_hdr);
+
+    // Append the relocated section header table and patch the ELF header.
+    let new_shoff = bytes.len();
+    bytes.extend_from_slice(&headers);
+    write_u64(&mut bytes, 0x28, new_shoff as u64);
+    write_u16(&mut bytes, 0x3c, (e_shnum + 1) as u16);
+
+    Ok(bytes)
+}
+
+// ---------------------------------------------------------------------------
+// Mach-O
+// ---------------------------------------------------------------------------
+
+const LC_SEGMENT_64: u32 = 0x19;
+const LC_SYMTAB: u32 = 0x2;
+const LC_DYSYMTAB: u32 = 0xb;
+const LC_DYLD_INFO: u32 = 0x22;
+const LC_DYLD_INFO_ONLY: u32 = 0x8000_0022;
+const LC_FUNCTION_STARTS: u32 = 0x26;
+const LC_DATA_IN_CODE: u32 = 0x29;
+const LC_CODE_SIGNATURE: u32 = 0x1d;
+const LC_SEGMENT_SPLIT_INFO: u32 = 0x1e;
+const LC_DYLIB_CODE_SIGN_DRS: u32 = 0x2b;
+const LC_LINKER_OPTIMIZATION_HINT: u32 = 0x2e;
+const LC_DYLD_EXPORTS_TRIE: u32 = 0x8000_0033;
+const LC_DYLD_CHAINED_FIXUPS: u32 = 0x8000_0034;
+const MACHO_PAGE: u64 = 0x4000;
+
+/// Inject a `NODE_SEA` segment containing a `NODE_SEA_BLOB` section into a
+/// 64-bit Mach-O.
+///
+/// The blob is inserted at the old `__LINKEDIT` file offset so `__LINKEDIT`
+/// stays the last segment (required for `codesign`); `__LINKEDIT` and every
+/// load command that points into it are shifted down by the inserted size. The
+/// new segment takes a fresh, page-aligned VM address above all existing
+/// segments — **not** a file offset — and the new `LC_SEGMENT_64` is written
+/// into the reserved padding after the load commands.
+fn inject_macho(mut bytes: Vec<u8>, blob: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    const SEG_CMD_SIZE: usize = 72;
+    const SECT_SIZE: usize = 80;
+
+    if read_u32(&bytes, 0) != 0xfeed_facf {
+        return Err("Unsupported Mach-O (only 64-bit little-endian handled)".into());
+    }
+
+    let ncmds = read_u32(&bytes, 16);
+    let sizeofcmds = read_u32(&bytes, 20) as usize;
+    let header_size = 32usize;
+    let cmds_end = header_size + sizeofcmds;
+
+    // Scan load commands for __LINKEDIT, the lowest section file offset and the
+    // highest VM address currently in use.
+    let mut linkedit_fileoff: Option<u64> = None;
+    let mut lowest_section_off = u64::MAX;
+    let mut max_vm_end = 0u64;
+    {
+        let mut offset = header_size;
+        for _ in 0..ncmds {
+            let cmd = read_u32(&bytes, offset);
+            let cmdsize = read_u32(&bytes, offset + 4) as usize;
+            if cmd == LC_SEGMENT_64 {
+                let segname = read_name16(&bytes, offset + 0x08);
+                let vmaddr = read_u64(&bytes, offset + 0x18);
+                let vmsize = read_u64(&bytes, offset + 0x20);
+                let fileoff = read_u64(&bytes, offset + 0x28);
+                max_vm_end = max_vm_end.max(vmaddr + vmsize);
+                if segname == "__LINKEDIT" {
+                    linkedit_fileoff = Some(fileoff);
+                }
+                let nsects = read_u32(&bytes, offset + 0x40);
+                for s in 0..nsects as usize {
+                    let sect = offset + SEG_CMD_SIZE + s * SECT_SIZE;
+                    let soff = read_u32(&bytes, sect + 0x30) as u64;
+                    if soff > 0 {
+                        lowest_section_off = lowest_section_off.min(soff);
+                    }
+                }
+            }
+            offset += cmdsize;
+        }
+    }
+
+    let insert_point = linkedit_fileoff.ok_or("Mach-O has no __LINKEDIT segment")? as usize;
+    let padded = align_up(blob.len() as u64, MACHO_PAGE);
+    let delta = padded; // bytes inserted at insert_point
+    let vmaddr = align_up(max_vm_end, MACHO_PAGE);
+
+    let cmd_size = SEG_CMD_SIZE + SECT_SIZE;
+    if (cmds_end + cmd_size) as u64 > lowest_section_off {
+        return Err("No room in Mach-O header to insert NODE_SEA load command".into());
+    }
+
+    // Shift every file offset that points at or after the insertion point.
+    shift_macho_offsets(&mut bytes, ncmds, insert_point as u64, delta);
+
+    // Splice the padded blob in at the old __LINKEDIT offset.
+    let mut inserted = blob.to_vec();
+    inserted.resize(padded as usize, 0);
+    let tail = bytes.split_off(insert_point);
+    bytes.extend_from_slice(&inserted);
+    bytes.extend_from_slice(&tail);
+
+    // Build the LC_SEGMENT_64 + section and write it into the reserved padding.
+    let mut cmd = vec![0u8; cmd_size];
+    write_u32(&mut cmd, 0x00, LC_SEGMENT_64);
+    write_u32(&mut cmd, 0x04, cmd_size as u32);
+    write_name16(&mut cmd, 0x08, MACHO_SEGMENT_NAME);
+    write_u64(&mut cmd, 0x18, vmaddr); // vmaddr (a VM address, not a file offset)
+    write_u64(&mut cmd, 0x20, padded); // vmsize
+    write_u64(&mut cmd, 0x28, insert_point as u64); // fileoff
+    write_u64(&mut cmd, 0x30, padded); // filesize
+    write_u32(&mut cmd, 0x38, 1); // maxprot = VM_PROT_READ
+    write_u32(&mut cmd, 0x3c, 1); // initprot = VM_PROT_READ
+    write_u32(&mut cmd, 0x40, 1); // nsects
+    let s = SEG_CMD_SIZE;
+    write_name16(&mut cmd, s + 0x00, BLOB_RESOURCE_NAME); // sectname
+    write_name16(&mut cmd, s + 0x10, MACHO_SEGMENT_NAME); // segname
+    write_u64(&mut cmd, s + 0x20, vmaddr); // addr
+    write_u64(&mut cmd, s + 0x28, blob.len() as u64); // size
+    write_u32(&mut cmd, s + 0x30, insert_point as u32); // offset
+
+    bytes[cmds_end..cmds_end + cmd_size].copy_from_slice(&cmd);
+
+    // Patch ncmds / sizeofcmds.
+    write_u32(&mut bytes, 16, ncmds + 1);
+    write_u32(&mut bytes, 20, (sizeofcmds + cmd_size) as u32);
+
+    Ok(bytes)
+}
+
+/// Add `delta` to every load-command file offset that lies at or beyond
+/// `insert_point`, keeping `__LINKEDIT` and its data consistent after the splice.
+fn shift_macho_offsets(bytes: &mut [u8], ncmds: u32, insert_point: u64, delta: u64) {
+    let mut offset = 32usize;
+    for _ in 0..ncmds {
+        let cmd = read_u32(bytes, offset);
+        let cmdsize = read_u32(bytes, offset + 4) as usize;
+
+        match cmd {
+            LC_SEGMENT_64 => {
+                shift_u64(bytes, offset + 0x28, insert_point, delta); // fileoff
+                let nsects = read_u32(bytes, offset + 0x40);
+                for s in 0..nsects as usize {
+                    let sect = offset + 72 + s * 80;
+                    shift_u32(bytes, sect + 0x30, insert_point, delta); // section offset
+                }
+            }
+            LC_SYMTAB => {
+                shift_u32(bytes, offset + 0x08, insert_point, delta); // symoff
+                shift_u32(bytes, offset + 0x10, insert_point, delta); // stroff
+            }
+            LC_DYSYMTAB => {
+                // tocoff, modtaboff, extrefsymoff, indirectsymoff, extreloff, locreloff
+                for field in [0x20, 0x28, 0x30, 0x38, 0x40, 0x48] {
+                    shift_u32(bytes, offset + field, insert_point, delta);
+                }
+            }
+            LC_DYLD_INFO | LC_DYLD_INFO_ONLY => {
+                for field in [0x08, 0x10, 0x18, 0x20, 0x28] {
+                    shift_u32(bytes, offset + field, insert_point, delta);
+                }
+            }
+            LC_FUNCTION_STARTS
+            | LC_DATA_IN_CODE
+            | LC_CODE_SIGNATURE
+            | LC_SEGMENT_SPLIT_INFO
+            | LC_DYLIB_CODE_SIGN_DRS
+            | LC_LINKER_OPTIMIZATION_HINT
+            | LC_DYLD_EXPORTS_TRIE
+            | LC_DYLD_CHAINED_FIXUPS => {
+                shift_u32(bytes, offset + 0x08, insert_point, delta); // dataoff
+            }
+            _ => {}
+        }
+
+        offset += cmdsize;
+    }
+}
+
+fn shift_u32(bytes: &mut [u8], off: usize, insert_point: u64, delta: u64) {
+    let value = read_u32(bytes, off) as u64;
+    if value >= insert_point && value != 0 {
+        write_u32(bytes, off, (value + delta) as u32);
+    }
+}
+
+fn shift_u64(bytes: &mut [u8], off: usize, insert_point: u64, delta: u64) {
+    let value = read_u64(bytes, off);
+    if value >= insert_point && value != 0 {
+        write_u64(bytes, off, value + delta);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PE
+// ---------------------------------------------------------------------------
+
+/// Append a section carrying the blob to a PE image, named `NODE_SEA`
+/// (`NODE_SEA_BLOB` cut to the 8-byte PE section-name limit): a new section header is
+/// written into the header gap after the existing table and the (file-aligned)
+/// blob is appended at EOF, with `NumberOfSections` and `SizeOfImage` updated.
+fn inject_pe(mut bytes: Vec<u8>, blob: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    const SECTION_HDR_SIZE: usize = 40;
+    // IMAGE_SCN_CNT_INITIALIZED_DATA | IMAGE_SCN_MEM_READ
+    const CHARACTERISTICS: u32 = 0x4000_0040;
+
+    let e_lfanew = read_u32(&bytes, 0x3c) as usize;
+    if &bytes[e_lfanew..e_lfanew + 4] != b"PE\0\0" {
+        return Err("Invalid PE signature".into());
+    }
+
+    let coff = e_lfanew + 4;
+    let num_sections = read_u16(&bytes, coff + 2) as usize;
+    let size_opt_hdr = read_u16(&bytes, coff + 16) as usize;
+    let opt = coff + 20;
+
+    let file_alignment = read_u32(&bytes, opt + 36);
+    let section_alignment = read_u32(&bytes, opt + 32);
+    let size_of_headers = read_u32(&bytes, opt + 60);
+
+    let sect_table = opt + size_opt_hdr;
+    let new_hdr_off = sect_table + num_sections * SECTION_HDR_SIZE;
+
+    // The new section header must fit within SizeOfHeaders, before the raw data
+    // of the first section begins.
+    if (new_hdr_off + SECTION_HDR_SIZE) as u32 > size_of_headers {
+        return Err("No room in PE header for an additional section".into());
+    }
+
+    // Virtual address: just past the last section, rounded to section alignment.
+    let mut max_virtual_end = 0u32;
+    for i in 0..num_sections {
+        let h = sect_table + i * SECTION_HDR_SIZE;
+        let vsize = read_u32(&bytes, h + 8);
+        let vaddr = read_u32(&bytes, h + 12);
+        max_virtual_end = max_virtual_end.max(align_up32(vaddr + vsize, section_alignment));
+    }
+
+    let raw_ptr = align_up32(bytes.len() as u32, file_alignment);
+    bytes.resize(raw_ptr as usize, 0);
+    let raw_size = align_up32(blob.len() as u32, file_alignment);
+    bytes.extend_from_slice(blob);
+    bytes.resize(raw_ptr as usize + raw_size as usize, 0);
+
+    write_name8(&mut bytes, new_hdr_off, BLOB_RESOURCE_NAME);
+    write_u32(&mut bytes, new_hdr_off + 8, blob.len() as u32); // VirtualSize
+    write_u32(&mut bytes, new_hdr_off + 12, max_virtual_end); // VirtualAddress
+    write_u32(&mut bytes, new_hdr_off + 16, raw_size); // SizeOfRawData
+    write_u32(&mut bytes, new_hdr_off + 20, raw_ptr); // PointerToRawData
+    write_u32(&mut bytes, new_hdr_off + 36, CHARACTERISTICS);
+
+    write_u16(&mut bytes, coff + 2, (num_sections + 1) as u16);
+    let size_of_image = align_up32(
+        max_virtual_end + align_up32(blob.len() as u32, section_alignment),
+        section_alignment,
+    );
+    write_u32(&mut bytes, opt + 56, size_of_image);
+
+    Ok(bytes)
+}
+
+// ---------------------------------------------------------------------------
+// little-endian helpers
+// ---------------------------------------------------------------------------
+
+fn read_u16(b: &[u8], o: usize) -> u16 {
+    u16::from_le_bytes([b[o], b[o + 1]])
+}
+fn read_u32(b: &[u8], o: usize) -> u32 {
+    u32::from_le_bytes([b[o], b[o + 1], b[o + 2], b[o + 3]])
+}
+fn read_u64(b: &[u8], o: usize) -> u64 {
+    let mut a = [0u8; 8];
+    a.copy_from_slice(&b[o..o + 8]);
+    u64::from_le_bytes(a)
+}
+fn write_u16(b: &mut [u8], o: usize, v: u16) {
+    b[o..o + 2].copy_from_slice(&v.to_le_bytes());
+}
+fn write_u32(b: &mut [u8], o: usize, v: u32) {
+    b[o..o + 4].copy_from_slice(&v.to_le_bytes());
+}
+fn write_u64(b: &mut [u8], o: usize, v: u64) {
+    b[o..o + 8].copy_from_slice(&v.to_le_bytes());
+}
+
+/// Write a NUL-padded fixed-width name (8 bytes, PE section names).
+fn write_name8(b: &mut [u8], o: usize, name: &str) {
+    let bytes = name.as_bytes();
+    let len = bytes.len().min(8);
+    b[o..o + len].copy_from_slice(&bytes[..len]);
+}
+
+/// Write a NUL-padded fixed-width name (16 bytes, Mach-O segname/sectname).
+fn write_name16(b: &mut [u8], o: usize, name: &str) {
+    let bytes = name.as_bytes();
+    let len = bytes.len().min(16);
+    b[o..o + len].copy_from_slice(&bytes[..len]);
+}
+
+/// Read a NUL-padded fixed-width 16-byte name (Mach-O segname/sectname).
+fn read_name16(b: &[u8], o: usize) -> String {
+    let slice = &b[o..o + 16];
+    let end = slice.iter().position(|&c| c == 0).unwrap_or(16);
+    String::from_utf8_lossy(&slice[..end]).into_owned()
+}
+
+fn align_up(value: u64, align: u64) -> u64 {
+    if align == 0 {
+        value
+    } else {
+        (value + align - 1) & !(align - 1)
+    }
+}
+
+fn align_up32(value: u32, align: u32) -> u32 {
+    if align == 0 {
+        value
+    } else {
+        (value + align - 1) & !(align - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object::read::{File, Object, ObjectSection};
+    use object::write::{Object as WriteObject, SectionKind};
+    use object::{Architecture, BinaryFormat, Endianness};
+
+    fn fuse_data() -> Vec<u8> {
+        // Matches how Node embeds the sentinel: `<fuse>:0`.
+        format!("{}:0", NODE_SEA_FUSE).into_bytes()
+    }
+
+    #[test]
+    fn find_subslice_locates_needle() {
+        assert_eq!(find_subslice(b"abcdef", b"cd"), Some(2));
+        assert_eq!(find_subslice(b"abcdef", b"xy"), None);
+        assert_eq!(find_subslice(b"abc", b""), None);
+    }
+
+    #[test]
+    fn blow_fuse_flips_the_digit() {
+        let mut bytes = fuse_data();
+        blow_fuse(&mut bytes).unwrap();
+        let flipped = String::from_utf8(bytes).unwrap();
+        assert!(flipped.ends_with(":1"));
+    }
+
+    #[test]
+    fn blow_fuse_errors_without_sentinel() {
+        let mut bytes = b"no sentinel here".to_vec();
+        assert!(blow_fuse(&mut bytes).is_err());
+    }
+
+    #[test]
+    fn inject_elf_round_trips_through_object() {
+        // Build a minimal ELF object carrying the fuse string, inject, then
+        // re-parse the patched bytes with `object` and assert the blob landed.
+        let mut obj = WriteObject::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+        let section = obj.add_section(Vec::new(), b".sea".to_vec(), SectionKind::Data);
+        obj.set_section_data(section, fuse_data(), 1);
+        let elf = obj.write().unwrap();
+
+        let blob = b"hello-sea-blob".to_vec();
+        let patched = inject_elf(elf, &blob).unwrap();
+
+        let parsed = File::parse(&*patched).unwrap();
+        let injected = parsed
+            .section_by_name("NODE_SEA_BLOB")
+            .expect("NODE_SEA_BLOB section present");
+        assert_eq!(injected.data().unwrap(), blob.as_slice());
+    }
+}
@@ -0,0 +1,6 @@
+//! Artifact storage backends.
+//!
+//! Currently S3-compatible object storage, used both to pull a source bundle
+//! from a bucket and to push the finished build artifact back.
+
+pub mod s3;
@@ -0,0 +1,148 @@
+//! S3-compatible storage backend built on `rust-s3` and `aws-creds`.
+//!
+//! Works against AWS as well as MinIO and DigitalOcean Spaces via a custom
+//! `endpoint`/`region`. A `source` of the form `s3://bucket/key` is fetched and
+//! unpacked into `s3_source` under the output dir by [`pull_source`]; finished
+//! artifacts are pushed with
+//! [`push_artifact`], which logs the object's SHA-256 for integrity.
+
+use s3::bucket::Bucket;
+use s3::creds::Credentials;
+use s3::region::Region;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::config::NpackConfig;
+
+/// Build an S3 [`Bucket`] handle for `bucket_name` from the configured
+/// credentials, region and (optional) custom endpoint.
+fn connect(bucket_name: &str, config: &NpackConfig) -> Result<Bucket, Box<dyn std::error::Error>> {
+    let key = config.get_s3_key().ok_or("Missing s3_key")?;
+    let secret = config.get_s3_secret().ok_or("Missing s3_secret")?;
+
+    let credentials = Credentials::new(Some(&key), Some(&secret), None, None, None)?;
+
+    // A custom endpoint implies an S3-compatible host (MinIO, DO Spaces) that
+    // needs path-style addressing rather than virtual-host buckets.
+    let custom_endpoint = config.get_s3_endpoint().is_some();
+    let region = match config.get_s3_endpoint() {
+        Some(endpoint) => Region::Custom {
+            region: config.get_s3_region(),
+            endpoint,
+        },
+        None => config.get_s3_region().parse()?,
+    };
+
+    let bucket = Bucket::new(bucket_name, region, credentials)?;
+    let bucket = if custom_endpoint {
+        bucket.with_path_style()
+    } else {
+        bucket
+    };
+    Ok(bucket)
+}
+
+/// Split an `s3://bucket/key` URL into its bucket and key components.
+fn parse_url(url: &str) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let rest = url
+        .strip_prefix("s3://")
+        .ok_or_else(|| format!("Not an s3:// URL: {}", url))?;
+    let (bucket, key) = rest
+        .split_once('/')
+        .ok_or_else(|| format!("s3 URL must be s3://bucket/key, got {}", url))?;
+    Ok((bucket.to_string(), key.to_string()))
+}
+
+/// Fetch the object at an `s3://bucket/key` URL and unpack it into a project
+/// directory under `dest_dir`, returning that directory.
+///
+/// The object is expected to be a source bundle (`.tar.gz`/`.tgz` or `.zip`);
+/// downstream steps (`install_dependencies`, `bundle_app`) need a directory
+/// containing `package.json`, not a single archive file.
+pub async fn pull_source(
+    url: &str,
+    config: &NpackConfig,
+    dest_dir: &Path,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let (bucket_name, key) = parse_url(url)?;
+    let bucket = connect(&bucket_name, config)?;
+
+    println!("   Pulling s3://{}/{}", bucket_name, key);
+    let response = bucket.get_object(&key).await?;
+    if response.status_code() != 200 {
+        return Err(format!("S3 GET failed with status {}", response.status_code()).into());
+    }
+
+    let source_dir = dest_dir.join("s3_source");
+    if source_dir.exists() {
+        fs::remove_dir_all(&source_dir)?;
+    }
+    fs::create_dir_all(&source_dir)?;
+
+    unpack(&key, response.bytes(), &source_dir)?;
+
+    println!("   ✓ Pulled and unpacked to {:?}", source_dir);
+    Ok(source_dir)
+}
+
+/// Unpack a fetched bundle into `dest`, dispatching on the key's extension.
+fn unpack(key: &str, bytes: &[u8], dest: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let lower = key.to_ascii_lowercase();
+    if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+        let tar = flate2::read::GzDecoder::new(bytes);
+        let mut archive = tar::Archive::new(tar);
+        archive.unpack(dest)?;
+    } else if lower.ends_with(".zip") {
+        let reader = std::io::Cursor::new(bytes);
+        let mut archive = zip::ZipArchive::new(reader)?;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            let Some(path) = entry.enclosed_name() else {
+                continue;
+            };
+            let out_path = dest.join(path);
+            if entry.is_dir() {
+                fs::create_dir_all(&out_path)?;
+            } else {
+                if let Some(parent) = out_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents)?;
+                fs::write(&out_path, contents)?;
+            }
+        }
+    } else {
+        return Err(format!("Unsupported S3 source bundle format: {}", key).into());
+    }
+    Ok(())
+}
+
+/// Upload `file` to `s3_bucket` under `key`, logging its content hash.
+pub async fn push_artifact(
+    file: &Path,
+    key: &str,
+    config: &NpackConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let bucket_name = config
+        .get_s3_bucket()
+        .ok_or("Missing s3_bucket for upload")?;
+    let bucket = connect(&bucket_name, config)?;
+
+    let bytes = std::fs::read(file)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    let hash = format!("{:x}", hasher.finalize());
+
+    println!("   Uploading {:?} to s3://{}/{}", file, bucket_name, key);
+    let response = bucket.put_object(key, &bytes).await?;
+    if response.status_code() != 200 {
+        return Err(format!("S3 PUT failed with status {}", response.status_code()).into());
+    }
+
+    println!("   ✓ Uploaded {} bytes (sha256: {})", bytes.len(), hash);
+    Ok(())
+}